@@ -0,0 +1,57 @@
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use warp::{Filter, Rejection};
+
+use crate::error::Error;
+use crate::store::Store;
+use crate::types::AccountId;
+
+/// Extracts the account tied to the `x-api-key` header, rejecting the request
+/// if the header is missing or doesn't match a registered token.
+pub fn with_auth(store: Store) -> impl Filter<Extract = (AccountId,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("x-api-key")
+        .and(warp::any().map(move || store.clone()))
+        .and_then(authenticate)
+}
+
+async fn authenticate(token: Option<String>, store: Store) -> Result<AccountId, Rejection> {
+    let token = token.ok_or_else(|| warp::reject::custom(Error::MissingAuthToken))?;
+
+    store
+        .storage
+        .get_account_id(&hash_token(&token))
+        .await
+        .map_err(|_| warp::reject::custom(Error::InvalidAuthToken))
+}
+
+#[derive(Serialize)]
+struct RegisterResponse {
+    api_key: String,
+}
+
+pub async fn register(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+    let token = generate_token();
+
+    store
+        .storage
+        .add_account(hash_token(&token))
+        .await
+        .map_err(warp::reject::custom)?;
+
+    Ok(warp::reply::json(&RegisterResponse { api_key: token }))
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use warp::{filters::{body::BodyDeserializeError, cors::CorsForbidden}, reject::Reject, Rejection, Reply};
+use warp::http::StatusCode;
+
+#[derive(Debug)]
+pub enum Error {
+    ParseError(std::num::ParseIntError),
+    MissingParameter,
+    InvalidArgumentsOrder,
+    QuestionNotFound,
+    AnswerNotFound,
+    InvalidVoteDelta,
+    Database(sqlx::Error),
+    MissingAuthToken,
+    InvalidAuthToken,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Error::ParseError(ref err) => {
+                write!(f, "Cannot parse parameter: {}", err)
+            },
+            Error::MissingParameter => write!(f, "Missing parameter"),
+            Error::InvalidArgumentsOrder => write!(f, "Order of arguments is invalid. 'Start' cannot be greater than 'end'"),
+            Error::QuestionNotFound => write!(f, "Question not found"),
+            Error::AnswerNotFound => write!(f, "Answer not found"),
+            Error::InvalidVoteDelta => write!(f, "'delta' must be 1 or -1"),
+            Error::Database(ref err) => write!(f, "Database query error: {}", err),
+            Error::MissingAuthToken => write!(f, "Missing x-api-key header"),
+            Error::InvalidAuthToken => write!(f, "No account found for this token"),
+        }
+    }
+}
+
+impl Reject for Error {}
+
+#[derive(Serialize)]
+struct ErrorMessage {
+    error: String,
+    code: u16,
+}
+
+fn reply_with(message: String, code: StatusCode) -> impl Reply {
+    warp::reply::with_status(
+        warp::reply::json(&ErrorMessage {
+            error: message,
+            code: code.as_u16(),
+        }),
+        code,
+    )
+}
+
+pub async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
+    if let Some(error) = r.find::<Error>() {
+        let code = match error {
+            Error::ParseError(_)
+            | Error::MissingParameter
+            | Error::InvalidArgumentsOrder
+            | Error::InvalidVoteDelta => StatusCode::BAD_REQUEST,
+            Error::QuestionNotFound | Error::AnswerNotFound => StatusCode::NOT_FOUND,
+            Error::MissingAuthToken | Error::InvalidAuthToken => StatusCode::UNAUTHORIZED,
+            Error::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Ok(reply_with(error.to_string(), code))
+    } else if let Some(error) = r.find::<CorsForbidden>() {
+        Ok(reply_with(error.to_string(), StatusCode::FORBIDDEN))
+    } else if let Some(error) = r.find::<BodyDeserializeError>() {
+        Ok(reply_with(error.to_string(), StatusCode::BAD_REQUEST))
+    } else if r.find::<warp::reject::MethodNotAllowed>().is_some() {
+        Ok(reply_with(
+            "Method not allowed".to_string(),
+            StatusCode::METHOD_NOT_ALLOWED,
+        ))
+    } else {
+        Ok(reply_with(
+            "Route not found".to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    }
+}
@@ -1,157 +1,147 @@
-use std::{collections::HashMap, sync::Arc, usize};
-use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
-use warp::{filters::{body::json, cors::CorsForbidden}, http::Method, reject::Reject, Filter, Rejection, Reply, http::StatusCode};
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Question {
-    id: QuestionId,
-    title: String,
-    content: String,
-    tags: Option<Vec<String>>,
-}
+use std::{collections::HashMap, sync::Arc};
+use warp::{http::Method, http::StatusCode, Filter, Rejection, Reply};
 
-#[derive(Debug, Serialize, Clone, Eq, Hash, PartialEq, Deserialize)]
-struct QuestionId(String);
+mod auth;
+mod error;
+mod metrics;
+mod store;
+mod types;
 
-impl std::fmt::Display for QuestionId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "id: {}", self.0)
-    }
-}
+use error::{return_error, Error};
+use metrics::Metrics;
+use store::{HashMapStorage, SqliteStorage, Store, Storage};
+use types::{extract_pagination, AccountId, Answer, AnswerId, Question, QuestionId, VoteRequest};
 
 async fn get_questions(
     params: HashMap<String, String>,
     store: Store
 ) -> Result<impl Reply, Rejection> {
-    if !params.is_empty() {
-        let pagination = extract_pagination(params)?;
-
-        let res: Vec<Question> = store
-            .questions
-            .read()
-            .await
-            .values()
-            .cloned()
-            .collect();
-        
-        let end_index = if pagination.end > res.len() {
-            res.len()
-        } else {
-            pagination.end
-        };
-
-        let res = &res[pagination.start..end_index];
-        return Ok(warp::reply::json(&res));
-    } else {
-        let res: Vec<Question> = store.questions.read().await.values().cloned().collect();
-        return Ok(warp::reply::json(&res));
-    }
+    let pagination = extract_pagination(&params)?;
+    let tag = params.get("tag").cloned();
+    let query = params.get("query").cloned();
+
+    let res = store
+        .storage
+        .get_questions(pagination, tag, query)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    Ok(warp::reply::json(&res))
 }
 
 async fn add_question(
+    _account_id: AccountId,
     store: Store,
     question: Question
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    store.questions.write().await.insert(question.id.clone(), question);
-    
-    return Ok(warp::reply::with_status("Question added", StatusCode::OK));
-}
+    store
+        .storage
+        .add_question(question)
+        .await
+        .map_err(warp::reject::custom)?;
 
-async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
-    if let Some(error) = r.find::<Error>() {
-        Ok(warp::reply::with_status(
-            error.to_string(), 
-            StatusCode::RANGE_NOT_SATISFIABLE,
-        ))
-    } else if let Some(error) = r.find::<CorsForbidden>() {
-        Ok(warp::reply::with_status(
-            error.to_string(), 
-            StatusCode::FORBIDDEN
-        ))
-    } else {
-        Ok(warp::reply::with_status(
-            "Route not found".to_string(), 
-            StatusCode::NOT_FOUND))
-    }
+    Ok(warp::reply::with_status("Question added", StatusCode::OK))
 }
 
+async fn update_question(
+    id: String,
+    _account_id: AccountId,
+    store: Store,
+    question: Question
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store
+        .storage
+        .update_question(QuestionId(id), question)
+        .await
+        .map_err(warp::reject::custom)?;
 
-#[derive(Clone)]
-struct Store {
-    questions: Arc<RwLock<HashMap<QuestionId, Question>>>,
+    Ok(warp::reply::with_status("Question updated", StatusCode::OK))
 }
-impl Store {
-    fn new() -> Self {
-        Store {
-            questions: Arc::new(RwLock::new(Self::init())),
-        }
-    }
 
-    fn add_question(mut self, question: Question) -> Self {
-        self.questions.insert(question.id.clone(), question);
-        return self;
-    }
-    
-    fn init() -> HashMap<QuestionId, Question> {
-        let file = include_str!("../questions.json");
-        return serde_json::from_str(file).expect("can't read questions.json");
-    }
-}
+async fn delete_question(
+    id: String,
+    _account_id: AccountId,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store
+        .storage
+        .delete_question(QuestionId(id))
+        .await
+        .map_err(warp::reject::custom)?;
 
-#[derive(Debug)]
-enum Error {
-    ParseError(std::num::ParseIntError),
-    MissingParameter,
-    InvalidArgumentsOrder,
+    Ok(warp::reply::with_status("Question deleted", StatusCode::OK))
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            Error::ParseError(ref err) => {
-                write!(f, "Cannot parse parameter: {}", err)
-            },
-            Error::MissingParameter => write!(f, "Missing parameter"),
-            Error::InvalidArgumentsOrder => write!(f, "Order of arguments is invalid. 'Start' cannot be greater than 'end'"),
-        }
-    }
+async fn add_answer(
+    store: Store,
+    answer: Answer
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store
+        .storage
+        .add_answer(answer)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    Ok(warp::reply::with_status("Answer added", StatusCode::OK))
 }
 
-impl Reject for Error {}
+async fn get_answers(
+    question_id: String,
+    store: Store
+) -> Result<impl Reply, Rejection> {
+    let res = store
+        .storage
+        .get_answers(QuestionId(question_id))
+        .await
+        .map_err(warp::reject::custom)?;
 
-#[derive(Debug)]
-struct Pagination {
-    start: usize,
-    end: usize,
+    Ok(warp::reply::json(&res))
 }
 
-fn extract_pagination(
-    params: HashMap<String, String>
-) -> Result<Pagination, Error> {
-    if let (Some(start), Some(end)) = (params.get("start"), params.get("end")) {
-
-        let start = start.parse::<usize>().map_err(Error::ParseError)?;
-        let end = end.parse::<usize>().map_err(Error::ParseError)?;
-
-        if end >= start {
-            Ok(Pagination { start, end })
-        } else {
-            Err(Error::InvalidArgumentsOrder)
-        }
-    } else {
-        Err(Error::MissingParameter)
+async fn vote_answer(
+    id: String,
+    store: Store,
+    vote: VoteRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if vote.delta != 1 && vote.delta != -1 {
+        return Err(warp::reject::custom(Error::InvalidVoteDelta));
     }
+
+    store
+        .storage
+        .vote_answer(AnswerId(id), vote.delta)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    Ok(warp::reply::with_status("Vote recorded", StatusCode::OK))
 }
 
 #[tokio::main]
 async fn main() {
-    let store = Store::new();
-    let store_filter = warp::any().map(move || store.clone());
-    
+    let storage: Arc<dyn Storage> = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => Arc::new(
+            SqliteStorage::new(&database_url)
+                .await
+                .expect("can't connect to DATABASE_URL"),
+        ),
+        Err(_) => Arc::new(HashMapStorage::new()),
+    };
+    let store = Store::new(storage);
+    let store_filter = {
+        let store = store.clone();
+        warp::any().map(move || store.clone())
+    };
+
+    let metrics = Metrics::new();
+    let metrics_filter = {
+        let metrics = metrics.clone();
+        warp::any().map(move || metrics.clone())
+    };
+
     let cors = warp::cors()
         .allow_any_origin()
         .allow_header("content-type")
+        .allow_header("x-api-key")
         .allow_methods(
             &[Method::PUT, Method::DELETE, Method::GET, Method::POST ]
         );
@@ -160,20 +150,82 @@ async fn main() {
         .and(warp::path("questions"))
         .and(warp::path::end())
         .and(warp::query())
-        .and(store_filter)
+        .and(store_filter.clone())
         .and_then(get_questions);
-    
+
     let add_question = warp::post()
         .and(warp::path("questions"))
         .and(warp::path::end())
+        .and(auth::with_auth(store.clone()))
         .and(store_filter.clone())
         .and(warp::body::json())
         .and_then(add_question);
-    
+
+    let update_question = warp::put()
+        .and(warp::path("questions"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(auth::with_auth(store.clone()))
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(update_question);
+
+    let delete_question = warp::delete()
+        .and(warp::path("questions"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(auth::with_auth(store.clone()))
+        .and(store_filter.clone())
+        .and_then(delete_question);
+
+    let register = warp::post()
+        .and(warp::path("register"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(auth::register);
+
+    let add_answer = warp::post()
+        .and(warp::path("answers"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(add_answer);
+
+    let get_answers = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param())
+        .and(warp::path("answers"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(get_answers);
+
+    let vote_answer = warp::post()
+        .and(warp::path("answers"))
+        .and(warp::path::param())
+        .and(warp::path("vote"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(vote_answer);
+
+    let metrics_route = warp::get()
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(metrics_filter)
+        .and_then(metrics::metrics_handler);
+
     let routes = get_questions
         .or(add_question)
+        .or(update_question)
+        .or(delete_question)
+        .or(register)
+        .or(add_answer)
+        .or(get_answers)
+        .or(vote_answer)
+        .or(metrics_route)
         .with(cors)
-        .recover(return_error);
+        .recover(return_error)
+        .with(metrics::log(metrics));
 
     warp::serve(routes)
         .run(([127, 0, 0, 1], 3030))
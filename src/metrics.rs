@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use warp::{Rejection, Reply};
+
+/// Path segments that are part of a route rather than an id, so the `route`
+/// label stays low-cardinality instead of growing one series per question id.
+const ROUTE_LITERALS: &[&str] = &["questions", "answers", "vote", "register", "metrics"];
+
+fn route_template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.is_empty() || ROUTE_LITERALS.contains(&segment) {
+                segment
+            } else {
+                ":id"
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Request counters and a latency histogram, gathered from a `warp::log::custom`
+/// wrapper around the composed routes and rendered at `GET /metrics`.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Arc<Registry>,
+    pub requests_total: IntCounterVec,
+    pub responses_total: IntCounterVec,
+    pub request_duration: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "Total number of HTTP requests per route"),
+            &["route"],
+        )
+        .expect("metric can be created");
+
+        let responses_total = IntCounterVec::new(
+            Opts::new(
+                "http_responses_total",
+                "Total number of HTTP responses per route and status code",
+            ),
+            &["route", "status"],
+        )
+        .expect("metric can be created");
+
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP handler latency in seconds",
+            ),
+            &["route"],
+        )
+        .expect("metric can be created");
+
+        registry
+            .register(Box::new(requests_total.clone()))
+            .expect("can register collector");
+        registry
+            .register(Box::new(responses_total.clone()))
+            .expect("can register collector");
+        registry
+            .register(Box::new(request_duration.clone()))
+            .expect("can register collector");
+
+        Metrics {
+            registry: Arc::new(registry),
+            requests_total,
+            responses_total,
+            request_duration,
+        }
+    }
+
+    fn render(&self) -> String {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .expect("can encode metrics");
+        String::from_utf8(buffer).expect("metrics are valid utf8")
+    }
+}
+
+pub async fn metrics_handler(metrics: Metrics) -> Result<impl Reply, Rejection> {
+    Ok(metrics.render())
+}
+
+/// A `warp::log::custom` wrapper that records request/response/latency
+/// metrics for every route in `metrics`, without authentication. Must be
+/// applied after `.recover(return_error)` so `info.status()` reflects the
+/// JSON error reply's status instead of warp's generic 500 for rejections.
+pub fn log(metrics: Metrics) -> warp::log::Log<impl Fn(warp::log::Info<'_>) + Clone> {
+    warp::log::custom(move |info| {
+        let route = route_template(info.path());
+        let status = info.status().as_u16().to_string();
+
+        metrics.requests_total.with_label_values(&[&route]).inc();
+        metrics
+            .responses_total
+            .with_label_values(&[&route, &status])
+            .inc();
+        metrics
+            .request_duration
+            .with_label_values(&[&route])
+            .observe(info.elapsed().as_secs_f64());
+    })
+}
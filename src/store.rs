@@ -0,0 +1,388 @@
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+
+use crate::error::Error;
+use crate::types::{AccountId, Answer, AnswerId, Pagination, Question, QuestionId};
+
+/// Storage is the abstraction every handler in `main.rs` goes through, so the
+/// in-memory store used for local development and the SQLite-backed store used
+/// in production share a single interface. Handlers reach it through the
+/// `Arc<dyn Storage>` in `Store` (dynamic dispatch); they take no type parameter.
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync {
+    async fn get_questions(
+        &self,
+        pagination: Option<Pagination>,
+        tag: Option<String>,
+        query: Option<String>,
+    ) -> Result<Vec<Question>, Error>;
+    async fn add_question(&self, question: Question) -> Result<(), Error>;
+    async fn update_question(&self, id: QuestionId, question: Question) -> Result<(), Error>;
+    async fn delete_question(&self, id: QuestionId) -> Result<(), Error>;
+
+    /// Stores `token_hash` as a freshly registered account and returns its id.
+    async fn add_account(&self, token_hash: String) -> Result<AccountId, Error>;
+    /// Looks an account up by the SHA-256 hash of its API key.
+    async fn get_account_id(&self, token_hash: &str) -> Result<AccountId, Error>;
+
+    /// Rejects with `Error::QuestionNotFound` if `answer.question_id` doesn't exist.
+    async fn add_answer(&self, answer: Answer) -> Result<(), Error>;
+    async fn get_answers(&self, question_id: QuestionId) -> Result<Vec<Answer>, Error>;
+    /// Atomically adjusts the stored answer's score by `delta`.
+    async fn vote_answer(&self, id: AnswerId, delta: i32) -> Result<(), Error>;
+}
+
+#[derive(Clone)]
+pub struct Store {
+    pub storage: Arc<dyn Storage>,
+}
+
+impl Store {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Store { storage }
+    }
+}
+
+/// The original `HashMap`-backed store, seeded from `questions.json`. Data does
+/// not survive a restart; use `SqliteStorage` for that.
+pub struct HashMapStorage {
+    questions: Arc<RwLock<HashMap<QuestionId, Question>>>,
+    users: Arc<RwLock<HashMap<String, AccountId>>>,
+    answers: Arc<RwLock<HashMap<AnswerId, Answer>>>,
+}
+
+impl HashMapStorage {
+    pub fn new() -> Self {
+        HashMapStorage {
+            questions: Arc::new(RwLock::new(Self::init())),
+            users: Arc::new(RwLock::new(HashMap::new())),
+            answers: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    fn init() -> HashMap<QuestionId, Question> {
+        let file = include_str!("../questions.json");
+        serde_json::from_str(file).expect("can't read questions.json")
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for HashMapStorage {
+    async fn get_questions(
+        &self,
+        pagination: Option<Pagination>,
+        tag: Option<String>,
+        query: Option<String>,
+    ) -> Result<Vec<Question>, Error> {
+        let mut res: Vec<Question> = self.questions.read().await.values().cloned().collect();
+
+        if let Some(tag) = tag {
+            res.retain(|q| q.tags.as_ref().is_some_and(|tags| tags.contains(&tag)));
+        }
+
+        if let Some(query) = query {
+            let query = query.to_lowercase();
+            res.retain(|q| {
+                q.title.to_lowercase().contains(&query) || q.content.to_lowercase().contains(&query)
+            });
+        }
+
+        match pagination {
+            Some(pagination) => {
+                // Filtering happens above, so `pagination.start`/`end` (validated
+                // only against each other) can both be past the end of the
+                // filtered set — clamp instead of indexing directly.
+                let start = pagination.start.min(res.len());
+                let end = pagination.end.min(res.len());
+                Ok(res[start..end].to_vec())
+            }
+            None => Ok(res),
+        }
+    }
+
+    async fn add_question(&self, question: Question) -> Result<(), Error> {
+        self.questions.write().await.insert(question.id.clone(), question);
+        Ok(())
+    }
+
+    async fn update_question(&self, id: QuestionId, question: Question) -> Result<(), Error> {
+        match self.questions.write().await.get_mut(&id) {
+            Some(q) => {
+                *q = question;
+                Ok(())
+            }
+            None => Err(Error::QuestionNotFound),
+        }
+    }
+
+    async fn delete_question(&self, id: QuestionId) -> Result<(), Error> {
+        match self.questions.write().await.remove(&id) {
+            Some(_) => Ok(()),
+            None => Err(Error::QuestionNotFound),
+        }
+    }
+
+    async fn add_account(&self, token_hash: String) -> Result<AccountId, Error> {
+        let account_id = AccountId(token_hash.clone());
+        self.users.write().await.insert(token_hash, account_id.clone());
+        Ok(account_id)
+    }
+
+    async fn get_account_id(&self, token_hash: &str) -> Result<AccountId, Error> {
+        self.users
+            .read()
+            .await
+            .get(token_hash)
+            .cloned()
+            .ok_or(Error::InvalidAuthToken)
+    }
+
+    async fn add_answer(&self, answer: Answer) -> Result<(), Error> {
+        if !self.questions.read().await.contains_key(&answer.question_id) {
+            return Err(Error::QuestionNotFound);
+        }
+
+        self.answers.write().await.insert(answer.id.clone(), answer);
+        Ok(())
+    }
+
+    async fn get_answers(&self, question_id: QuestionId) -> Result<Vec<Answer>, Error> {
+        Ok(self
+            .answers
+            .read()
+            .await
+            .values()
+            .filter(|a| a.question_id == question_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn vote_answer(&self, id: AnswerId, delta: i32) -> Result<(), Error> {
+        match self.answers.write().await.get_mut(&id) {
+            Some(answer) => {
+                answer.score += delta;
+                Ok(())
+            }
+            None => Err(Error::AnswerNotFound),
+        }
+    }
+}
+
+/// A `DATABASE_URL`-selected store backed by SQLite. Pagination is pushed down
+/// to `LIMIT`/`OFFSET` instead of slicing a fully-materialized `Vec`.
+pub struct SqliteStorage {
+    pool: sqlx::SqlitePool,
+}
+
+impl SqliteStorage {
+    pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(database_url)
+            .await?;
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        Ok(SqliteStorage { pool })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct QuestionRow {
+    id: String,
+    title: String,
+    content: String,
+    tags: Option<String>,
+}
+
+impl From<QuestionRow> for Question {
+    fn from(row: QuestionRow) -> Self {
+        Question {
+            id: QuestionId(row.id),
+            title: row.title,
+            content: row.content,
+            tags: row.tags.map(|t| serde_json::from_str(&t).unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AnswerRow {
+    id: String,
+    content: String,
+    question_id: String,
+    score: i32,
+}
+
+impl From<AnswerRow> for Answer {
+    fn from(row: AnswerRow) -> Self {
+        Answer {
+            id: AnswerId(row.id),
+            content: row.content,
+            question_id: QuestionId(row.question_id),
+            score: row.score,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for SqliteStorage {
+    async fn get_questions(
+        &self,
+        pagination: Option<Pagination>,
+        tag: Option<String>,
+        query: Option<String>,
+    ) -> Result<Vec<Question>, Error> {
+        // Filter and pagination are optional, so the WHERE/LIMIT clauses are
+        // built up at runtime instead of using the `query!` compile-time macro.
+        let mut sql = String::from("SELECT id, title, content, tags FROM questions");
+        let mut clauses = Vec::new();
+        if tag.is_some() {
+            // Matches on an exact element of the `tags` JSON array, the same as
+            // `HashMapStorage`'s `tags.contains(&tag)` — a substring LIKE would
+            // also match "javascript" against a query for "java".
+            clauses.push("EXISTS (SELECT 1 FROM json_each(questions.tags) WHERE json_each.value = ?)");
+        }
+        if query.is_some() {
+            clauses.push("(LOWER(title) LIKE '%' || LOWER(?) || '%' OR LOWER(content) LIKE '%' || LOWER(?) || '%')");
+        }
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        if pagination.is_some() {
+            sql.push_str(" LIMIT ? OFFSET ?");
+        }
+
+        let mut q = sqlx::query_as::<_, QuestionRow>(&sql);
+        if let Some(ref tag) = tag {
+            q = q.bind(tag);
+        }
+        if let Some(ref query) = query {
+            q = q.bind(query).bind(query);
+        }
+        if let Some(pagination) = pagination {
+            q = q
+                .bind((pagination.end - pagination.start) as i64)
+                .bind(pagination.start as i64);
+        }
+
+        let rows = q.fetch_all(&self.pool).await.map_err(Error::Database)?;
+
+        Ok(rows.into_iter().map(Question::from).collect())
+    }
+
+    async fn add_question(&self, question: Question) -> Result<(), Error> {
+        let tags = question.tags.map(|t| serde_json::to_string(&t).unwrap_or_default());
+        sqlx::query("INSERT INTO questions (id, title, content, tags) VALUES (?, ?, ?, ?)")
+            .bind(question.id.0)
+            .bind(question.title)
+            .bind(question.content)
+            .bind(tags)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    async fn update_question(&self, id: QuestionId, question: Question) -> Result<(), Error> {
+        let tags = question.tags.map(|t| serde_json::to_string(&t).unwrap_or_default());
+        let result = sqlx::query("UPDATE questions SET title = ?, content = ?, tags = ? WHERE id = ?")
+            .bind(question.title)
+            .bind(question.content)
+            .bind(tags)
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::QuestionNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_question(&self, id: QuestionId) -> Result<(), Error> {
+        let result = sqlx::query("DELETE FROM questions WHERE id = ?")
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::QuestionNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn add_account(&self, token_hash: String) -> Result<AccountId, Error> {
+        sqlx::query("INSERT INTO users (token_hash) VALUES (?)")
+            .bind(&token_hash)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(AccountId(token_hash))
+    }
+
+    async fn get_account_id(&self, token_hash: &str) -> Result<AccountId, Error> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT token_hash FROM users WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        row.map(|(token_hash,)| AccountId(token_hash))
+            .ok_or(Error::InvalidAuthToken)
+    }
+
+    async fn add_answer(&self, answer: Answer) -> Result<(), Error> {
+        let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM questions WHERE id = ?")
+            .bind(&answer.question_id.0)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        if exists.is_none() {
+            return Err(Error::QuestionNotFound);
+        }
+
+        sqlx::query("INSERT INTO answers (id, content, question_id, score) VALUES (?, ?, ?, ?)")
+            .bind(answer.id.0)
+            .bind(answer.content)
+            .bind(answer.question_id.0)
+            .bind(answer.score)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    async fn get_answers(&self, question_id: QuestionId) -> Result<Vec<Answer>, Error> {
+        let rows: Vec<AnswerRow> =
+            sqlx::query_as("SELECT id, content, question_id, score FROM answers WHERE question_id = ?")
+                .bind(question_id.0)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+
+        Ok(rows.into_iter().map(Answer::from).collect())
+    }
+
+    async fn vote_answer(&self, id: AnswerId, delta: i32) -> Result<(), Error> {
+        let result = sqlx::query("UPDATE answers SET score = score + ? WHERE id = ?")
+            .bind(delta)
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::AnswerNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Question {
+    pub id: QuestionId,
+    pub title: String,
+    pub content: String,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Clone, Eq, Hash, PartialEq, Deserialize)]
+pub struct QuestionId(pub String);
+
+impl std::fmt::Display for QuestionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "id: {}", self.0)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Eq, Hash, PartialEq, Deserialize)]
+pub struct AccountId(pub String);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Answer {
+    pub id: AnswerId,
+    pub content: String,
+    pub question_id: QuestionId,
+    pub score: i32,
+}
+
+#[derive(Debug, Serialize, Clone, Eq, Hash, PartialEq, Deserialize)]
+pub struct AnswerId(pub String);
+
+#[derive(Debug, Deserialize)]
+pub struct VoteRequest {
+    pub delta: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// `start`/`end` are optional so that `?tag=rust` or `?query=foo` work without
+/// pagination params. Providing only one of the pair is still an error.
+pub fn extract_pagination(
+    params: &HashMap<String, String>
+) -> Result<Option<Pagination>, Error> {
+    match (params.get("start"), params.get("end")) {
+        (Some(start), Some(end)) => {
+            let start = start.parse::<usize>().map_err(Error::ParseError)?;
+            let end = end.parse::<usize>().map_err(Error::ParseError)?;
+
+            if end >= start {
+                Ok(Some(Pagination { start, end }))
+            } else {
+                Err(Error::InvalidArgumentsOrder)
+            }
+        }
+        (None, None) => Ok(None),
+        _ => Err(Error::MissingParameter),
+    }
+}